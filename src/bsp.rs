@@ -1,5 +1,6 @@
 pub mod gpio;
 pub mod uart;
+pub mod memory_map;
 pub mod mmio;
 
 use cortex_a::asm;
@@ -19,12 +20,30 @@ pub fn drivers() -> [&'static dyn Driver; 2] {
 }
 
 pub fn post_init() {
-    unsafe { GPIO.map_pl011_uart() }
+    unsafe {
+        GPIO.map_pl011_uart();
+        UART.init_irq();
+    }
 }
 
+// TODO: this kernel has no interrupt controller driver or exception vector table yet, so
+// `uart::PL011Uart::handle_rx_irq` is never actually invoked. Call it from the IRQ vector once
+// one exists.
+
 #[no_mangle]
 extern "C" fn _start() -> ! {
-    kernel::init()
+    // See `kernel::chainloader`'s module docs: this is experimental and unsafe on real hardware
+    // without a relocation stub that doesn't exist yet, hence the extra
+    // `chainloader_unsafe_self_overwrite` feature required to build it in at all.
+    #[cfg(feature = "chainloader")]
+    {
+        kernel::chainloader::run()
+    }
+
+    #[cfg(not(feature = "chainloader"))]
+    {
+        kernel::init()
+    }
 }
 
 pub fn spin_for_cycles(cycles: usize) {
@@ -5,9 +5,11 @@
 //! GPIO driver.
 
 use core::ops;
-use register::{mmio::ReadWrite, register_bitfields, register_structs};
+use register::{mmio::{ReadWrite, ReadOnly, WriteOnly}, register_bitfields, register_structs};
 
-use crate::kernel::driver;
+use crate::{bsp::mmio, kernel::driver};
+
+pub mod pin;
 
 // GPIO registers.
 //
@@ -60,6 +62,12 @@ register_structs! {
         (0x10 => GPFSEL4: ReadWrite<u32>),
         (0x14 => GPFSEL5: ReadWrite<u32>),
         (0x18 => _reserved1),
+        (0x1C => GPSET0: WriteOnly<u32>),
+        (0x20 => _reserved2),
+        (0x28 => GPCLR0: WriteOnly<u32>),
+        (0x2C => _reserved3),
+        (0x34 => GPLEV0: ReadOnly<u32>),
+        (0x38 => _reserved4),
         (0x94 => GPPUD: ReadWrite<u32>),
         (0x98 => GPPUDCLK0: ReadWrite<u32, GPPUDCLK0::Register>),
         (0x9C => GPPUDCLK1: ReadWrite<u32>),
@@ -72,7 +80,7 @@ pub struct GPIO;
 
 impl GPIO {
     fn ptr(&self) -> *const RegisterBlock {
-        self.base_addr as *const _
+        mmio::GPIO_BASE as *const _
     }
 
     pub fn map_pl011_uart(&self) {
@@ -93,6 +101,11 @@ impl GPIO {
 
         self.GPPUDCLK0.set(0);
     }
+
+    /// Access GPIO pin `N` through the typestate [`pin::Pin`] API.
+    pub fn pin<const N: usize>(&self) -> pin::Pin<N, pin::Uninit> {
+        pin::Pin::new()
+    }
 }
 
 impl ops::Deref for GPIO {
@@ -0,0 +1,157 @@
+//! Typestate GPIO pin API.
+//!
+//! A [`Pin`] carries its mode (uninitialized, output, input, ...) in its type, so e.g. calling
+//! `set_high()` on a pin that hasn't been turned into an output is a compile error rather than a
+//! runtime one. `GPIO::map_pl011_uart` predates this and still pokes `GPFSEL1` directly; new code
+//! should go through `GPIO::pin` instead.
+//!
+//! Only GPIO bank 0 (pins 0-31) is wired up, since that covers every pin broken out on the RPi3
+//! and RPi4 header.
+
+use core::marker::PhantomData;
+
+use crate::bsp::{self, mmio};
+
+use super::RegisterBlock;
+
+/// Pin has not yet been configured.
+pub struct Uninit;
+
+/// Pin drives `GPSET0`/`GPCLR0`.
+pub struct Output;
+
+/// Pin reads `GPLEV0`, pull resistor disabled.
+pub struct Input;
+
+/// Pin reads `GPLEV0`, pull resistor enabled.
+pub struct PullInput;
+
+/// Pin is routed to one of the peripheral alternate functions.
+pub struct AltFuncMode;
+
+/// `GPFSELn` alternate function encoding, shared by all pins.
+#[derive(Copy, Clone, Debug)]
+pub enum AltFunc {
+    Func0 = 0b100,
+    Func1 = 0b101,
+    Func2 = 0b110,
+    Func3 = 0b111,
+    Func4 = 0b011,
+    Func5 = 0b010,
+}
+
+const FSEL_INPUT: u32 = 0b000;
+const FSEL_OUTPUT: u32 = 0b001;
+
+const PULL_OFF: u32 = 0b00;
+const PULL_DOWN: u32 = 0b01;
+const PULL_UP: u32 = 0b10;
+
+/// GPIO pin `N`, typestated on its current mode.
+pub struct Pin<const N: usize, State> {
+    _state: PhantomData<State>,
+}
+
+impl<const N: usize> Pin<N, Uninit> {
+    /// Compile-time bound check, since `N` is known at compile time and a `debug_assert!` would
+    /// vanish in release builds, letting an out-of-bank pin wrap the shift in `set_high`/
+    /// `set_low`/`is_high` instead of failing loudly.
+    const BANK0_ASSERT: () = assert!(N < 32, "GPIO pin is outside bank 0 (pins 0-31)");
+
+    pub(super) fn new() -> Self {
+        let () = Self::BANK0_ASSERT;
+        Pin { _state: PhantomData }
+    }
+
+    pub fn into_output(self) -> Pin<N, Output> {
+        Self::set_func(FSEL_OUTPUT);
+        Pin { _state: PhantomData }
+    }
+
+    pub fn into_input(self) -> Pin<N, Input> {
+        Self::set_func(FSEL_INPUT);
+        Self::set_pull(PULL_OFF);
+        Pin { _state: PhantomData }
+    }
+
+    pub fn into_pull_up_input(self) -> Pin<N, PullInput> {
+        Self::set_func(FSEL_INPUT);
+        Self::set_pull(PULL_UP);
+        Pin { _state: PhantomData }
+    }
+
+    pub fn into_pull_down_input(self) -> Pin<N, PullInput> {
+        Self::set_func(FSEL_INPUT);
+        Self::set_pull(PULL_DOWN);
+        Pin { _state: PhantomData }
+    }
+
+    pub fn into_alt_func(self, func: AltFunc) -> Pin<N, AltFuncMode> {
+        Self::set_func(func as u32);
+        Pin { _state: PhantomData }
+    }
+}
+
+impl<const N: usize> Pin<N, Output> {
+    pub fn set_high(&self) {
+        Self::regs().GPSET0.set(1 << N);
+    }
+
+    pub fn set_low(&self) {
+        Self::regs().GPCLR0.set(1 << N);
+    }
+}
+
+impl<const N: usize> Pin<N, Input> {
+    pub fn is_high(&self) -> bool {
+        Self::regs().GPLEV0.get() & (1 << N) != 0
+    }
+}
+
+impl<const N: usize> Pin<N, PullInput> {
+    pub fn is_high(&self) -> bool {
+        Self::regs().GPLEV0.get() & (1 << N) != 0
+    }
+}
+
+impl<const N: usize, State> Pin<N, State> {
+    fn ptr() -> *const RegisterBlock {
+        mmio::GPIO_BASE as *const _
+    }
+
+    fn regs() -> &'static RegisterBlock {
+        unsafe { &*Self::ptr() }
+    }
+
+    /// Program the `GPFSELn` field for pin `N`: register index `n = N / 10`, bit offset
+    /// `(N % 10) * 3`.
+    fn set_func(value: u32) {
+        let shift = ((N % 10) * 3) as u32;
+        let mask = 0b111u32 << shift;
+        let regs = Self::regs();
+        let apply = |current: u32| (current & !mask) | (value << shift);
+
+        match N / 10 {
+            0 => regs.GPFSEL0.set(apply(regs.GPFSEL0.get())),
+            1 => regs.GPFSEL1.set(apply(regs.GPFSEL1.get())),
+            2 => regs.GPFSEL2.set(apply(regs.GPFSEL2.get())),
+            3 => regs.GPFSEL3.set(apply(regs.GPFSEL3.get())),
+            // `Pin::new`'s `BANK0_ASSERT` guarantees N < 32 at compile time, so N / 10 <= 3.
+            _ => unreachable!("GPIO pin {} is outside bank 0", N),
+        }
+    }
+
+    /// Drive the `GPPUD`/`GPPUDCLK0` pull sequence for pin `N`.
+    fn set_pull(pull: u32) {
+        let regs = Self::regs();
+
+        regs.GPPUD.set(pull);
+        bsp::spin_for_cycles(150);
+
+        regs.GPPUDCLK0.set(1 << N);
+        bsp::spin_for_cycles(150);
+
+        regs.GPPUD.set(0);
+        regs.GPPUDCLK0.set(0);
+    }
+}
@@ -0,0 +1,22 @@
+//! Per-board MMIO memory map, selected at compile time via the `rpi3`/`rpi4` features.
+
+#[cfg(feature = "rpi3")]
+const PERIPHERAL_BASE: usize = 0x3F00_0000;
+
+#[cfg(feature = "rpi4")]
+const PERIPHERAL_BASE: usize = 0xFE00_0000;
+
+#[cfg(not(any(feature = "rpi3", feature = "rpi4")))]
+compile_error!("exactly one of the \"rpi3\" or \"rpi4\" features must be enabled");
+
+#[cfg(all(feature = "rpi3", feature = "rpi4"))]
+compile_error!("\"rpi3\" and \"rpi4\" features are mutually exclusive");
+
+/// GPIO controller offset from the peripheral base, identical on RPi3 and RPi4.
+const GPIO_OFFSET: usize = 0x0020_0000;
+
+/// PL011 UART offset from the peripheral base, identical on RPi3 and RPi4.
+const UART_OFFSET: usize = 0x0020_1000;
+
+pub const GPIO_BASE: usize = PERIPHERAL_BASE + GPIO_OFFSET;
+pub const UART_BASE: usize = PERIPHERAL_BASE + UART_OFFSET;
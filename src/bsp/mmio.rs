@@ -0,0 +1,3 @@
+//! Peripheral base addresses for the board selected via `bsp::memory_map`.
+
+pub use super::memory_map::{GPIO_BASE, UART_BASE};
@@ -5,6 +5,7 @@
 //! PL011 UART driver.
 
 use core::ops;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use register::{mmio::{WriteOnly, ReadOnly, ReadWrite}, register_bitfields, register_structs};
 
 use crate::{ bsp::{self, mmio }, kernel::{io, result::{ KernelError, KernelResult}, driver} };
@@ -106,6 +107,25 @@ register_bitfields! {
         FEN OFFSET(4) NUMBITS(1) [
             FifosDisabled = 0,
             FifosEnabled = 1
+        ],
+
+        /// Two stop bits select. If this bit is set to 1, two stop bits are transmitted at the
+        /// end of the frame.
+        STP2 OFFSET(3) NUMBITS(1) [
+            OneStopBit = 0,
+            TwoStopBits = 1
+        ],
+
+        /// Even parity select. Controls the type of parity the UART uses when PEN is set.
+        EPS OFFSET(2) NUMBITS(1) [
+            Odd = 0,
+            Even = 1
+        ],
+
+        /// Parity enable. If this bit is set to 1, parity checking and generation is enabled.
+        PEN OFFSET(1) NUMBITS(1) [
+            Disabled = 0,
+            Enabled = 1
         ]
     ],
 
@@ -136,10 +156,62 @@ register_bitfields! {
         ]
     ],
 
+    /// Interrupt FIFO Level Select Register
+    IFLS [
+        /// Receive interrupt FIFO level select. The trigger points are when the FIFO is at the
+        /// given fraction of its 16-byte depth.
+        RXIFLSEL OFFSET(3) NUMBITS(3) [
+            OneEighth = 0b000,
+            OneQuarter = 0b001,
+            Half = 0b010,
+            ThreeQuarters = 0b011,
+            SevenEighths = 0b100
+        ]
+    ],
+
+    /// Interrupt Mask Set/Clear Register
+    IMSC [
+        /// Receive interrupt mask. Setting this bit enables the RX FIFO level interrupt.
+        RXIM OFFSET(4) NUMBITS(1) [
+            Disabled = 0,
+            Enabled = 1
+        ]
+    ],
+
+    /// Masked Interrupt Status Register
+    MIS [
+        /// Receive masked interrupt status. Reads as 1 when the RX interrupt is pending.
+        RXMIS OFFSET(4) NUMBITS(1) []
+    ],
+
     /// Interrupt Clear Register
     ICR [
         /// Meta field for all pending interrupts
         ALL OFFSET(0) NUMBITS(11) []
+    ],
+
+    /// Integration Test Control Register
+    ITCR [
+        /// Enables the integration test FIFO mode, in which `TDR` inserts bytes directly into
+        /// the RX FIFO instead of driving the physical RXD line.
+        ITCR OFFSET(0) NUMBITS(1) [
+            Disabled = 0,
+            Enabled = 1
+        ]
+    ],
+
+    /// Integration Test Input Register
+    ///
+    /// Supplies scan-chain input for modem lines (nUARTCTS and friends) while in test mode. This
+    /// driver only primes the RX FIFO and has no modem lines to drive, so it is left untouched.
+    ITIP [
+        DATA OFFSET(0) NUMBITS(4) []
+    ],
+
+    /// Test Data Register
+    TDR [
+        /// Byte inserted into the RX FIFO (or read from the TX FIFO) while `ITCR::ITCR` is set.
+        DATA OFFSET(0) NUMBITS(8) []
     ]
 }
 
@@ -154,12 +226,119 @@ register_structs! {
         (0x28 => FBRD: WriteOnly<u32, FBRD::Register>),
         (0x2c => LCRH: WriteOnly<u32, LCRH::Register>),
         (0x30 => CR: WriteOnly<u32, CR::Register>),
-        (0x34 => _reserved3),
+        (0x34 => IFLS: ReadWrite<u32, IFLS::Register>),
+        (0x38 => IMSC: ReadWrite<u32, IMSC::Register>),
+        (0x3c => _reserved3),
+        (0x40 => MIS: ReadOnly<u32, MIS::Register>),
         (0x44 => ICR: WriteOnly<u32, ICR::Register>),
-        (0x48 => @END),
+        (0x48 => _reserved4),
+        (0x80 => ITCR: ReadWrite<u32, ITCR::Register>),
+        (0x84 => ITIP: ReadWrite<u32, ITIP::Register>),
+        (0x88 => _reserved5),
+        (0x8c => TDR: WriteOnly<u32, TDR::Register>),
+        (0x90 => @END),
     }
 }
 
+/// Number of data bits per frame.
+#[derive(Copy, Clone, Debug)]
+pub enum WordLength {
+    Five,
+    Six,
+    Seven,
+    Eight,
+}
+
+/// Parity mode.
+#[derive(Copy, Clone, Debug)]
+pub enum Parity {
+    None,
+    Even,
+    Odd,
+}
+
+/// Number of stop bits.
+#[derive(Copy, Clone, Debug)]
+pub enum StopBits {
+    One,
+    Two,
+}
+
+/// Runtime UART configuration: baud rate, the clock it is derived from, and frame format.
+#[derive(Copy, Clone, Debug)]
+pub struct UartConfig {
+    pub baud: u32,
+    pub uart_clk: u32,
+    pub word_len: WordLength,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+}
+
+impl Default for UartConfig {
+    /// 8N1 at 230400 baud, assuming the firmware set the UART clock to 48 MHz.
+    fn default() -> Self {
+        UartConfig {
+            baud: 230_400,
+            uart_clk: 48_000_000,
+            word_len: WordLength::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+        }
+    }
+}
+
+/// Capacity of the RX ring buffer the IRQ handler fills.
+const RX_QUEUE_LEN: usize = 256;
+
+/// Single-producer (IRQ handler), single-consumer (`io::Read`) byte queue.
+struct RxQueue {
+    buf: [u8; RX_QUEUE_LEN],
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+impl RxQueue {
+    const fn new() -> Self {
+        RxQueue {
+            buf: [0; RX_QUEUE_LEN],
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Called from the IRQ handler. Drops the byte if the queue is full.
+    fn push(&mut self, byte: u8) {
+        let head = self.head.load(Ordering::Relaxed);
+        let next = (head + 1) % RX_QUEUE_LEN;
+
+        if next == self.tail.load(Ordering::Acquire) {
+            return;
+        }
+
+        self.buf[head] = byte;
+        self.head.store(next, Ordering::Release);
+    }
+
+    /// Called from `io::Read`. Returns `None` if no byte has arrived yet.
+    fn pop(&mut self) -> Option<u8> {
+        let tail = self.tail.load(Ordering::Relaxed);
+
+        if tail == self.head.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let byte = self.buf[tail];
+        self.tail.store((tail + 1) % RX_QUEUE_LEN, Ordering::Release);
+        Some(byte)
+    }
+}
+
+static mut RX_QUEUE: RxQueue = RxQueue::new();
+
+/// Number of dummy bytes primed into the RX FIFO so the 1/8 fill-level threshold trips on the
+/// very first real byte, decremented by the IRQ handler as it discards them.
+static mut RX_PRIME_PENDING: usize = 0;
+
 pub struct PL011Uart;
 
 impl ops::Deref for PL011Uart {
@@ -175,36 +354,140 @@ impl PL011Uart {
     fn ptr(&self) -> *const RegisterBlock {
         mmio::UART_BASE as *const _
     }
-}
-
-impl driver::Driver for PL011Uart {
-    fn name(&self) -> &str {
-        "PL011Uart"
-    }
 
-    /// Set up baud rate and characteristics.
+    /// Set up baud rate and frame format from a runtime `UartConfig`.
     ///
-    /// Results in 8N1 and 230400 baud (if the clk has been previously set to 48 MHz by the
-    /// firmware).
-    fn init(&self) -> KernelResult {
+    /// The integer/fractional baud rate divisors are computed without floating point, per the
+    /// PL011 TRM: `baud_div = (uart_clk * 4) / baud` is a 16.6 fixed-point value whose integer
+    /// part is `IBRD` and whose low 6 bits are the `FBRD` fractional divisor.
+    pub fn configure(&self, cfg: &UartConfig) -> KernelResult {
+        if cfg.baud == 0 {
+            return Err(KernelError::InvalidConfig);
+        }
+
+        let baud_div = (cfg.uart_clk * 4) / cfg.baud;
+        let ibrd = baud_div >> 6;
+        let fbrd = baud_div & 0x3F;
+
+        if ibrd == 0 || ibrd > 0xFFFF {
+            return Err(KernelError::InvalidConfig);
+        }
+
         // UART init state
         self.CR.set(0);
         self.ICR.write(ICR::ALL::CLEAR);
-        
+
         // Set baud rate
-        self.IBRD.write(IBRD::IBRD.val(13));
-        self.FBRD.write(FBRD::FBRD.val(2));
-        
-        // Set 8-bit as data size and enable FIFO
+        self.IBRD.write(IBRD::IBRD.val(ibrd));
+        self.FBRD.write(FBRD::FBRD.val(fbrd));
+
+        // Set data size, stop bits and parity, and enable FIFO
+        let word_len = match cfg.word_len {
+            WordLength::Five => LCRH::WLEN::FiveBit,
+            WordLength::Six => LCRH::WLEN::SixBit,
+            WordLength::Seven => LCRH::WLEN::SevenBit,
+            WordLength::Eight => LCRH::WLEN::EightBit,
+        };
+        let stop_bits = match cfg.stop_bits {
+            StopBits::One => LCRH::STP2::OneStopBit,
+            StopBits::Two => LCRH::STP2::TwoStopBits,
+        };
+        let parity = match cfg.parity {
+            Parity::None => LCRH::PEN::Disabled,
+            Parity::Even => LCRH::PEN::Enabled + LCRH::EPS::Even,
+            Parity::Odd => LCRH::PEN::Enabled + LCRH::EPS::Odd,
+        };
         self.LCRH
-            .write(LCRH::WLEN::EightBit + LCRH::FEN::FifosEnabled); // 8N1 + Fifo on
-        
+            .write(word_len + LCRH::FEN::FifosEnabled + stop_bits + parity);
+
         // Enable UART, enable RW
         self.CR
             .write(CR::UARTEN::Enabled + CR::TXE::Enabled + CR::RXE::Enabled);
 
         Ok(())
     }
+
+    /// Number of dummy bytes needed to prime the RX FIFO up to one below the 1/8 threshold.
+    const RX_FIFO_PRIME_BYTES: usize = 1;
+
+    /// Switch the console over to interrupt-driven receive.
+    ///
+    /// Must run after [`Self::configure`]. Sets the RX interrupt to fire at the 1/8 FIFO
+    /// fill-level and, on real hardware, primes the FIFO so that threshold trips on the very
+    /// first byte received (see [`Self::prime_rx_fifo`]).
+    pub fn init_irq(&self) {
+        self.IFLS.write(IFLS::RXIFLSEL::OneEighth);
+
+        #[cfg(not(feature = "qemu"))]
+        self.prime_rx_fifo(Self::RX_FIFO_PRIME_BYTES);
+
+        self.IMSC.write(IMSC::RXIM::Enabled);
+    }
+
+    /// Pre-load `count` dummy bytes into the RX FIFO via the integration test registers.
+    ///
+    /// The PL011 only raises an RX interrupt when the FIFO crosses one of its fixed fill-level
+    /// fractions (1/8 at the lowest), never on the first byte in an empty FIFO. Priming the FIFO
+    /// to one byte below that threshold works around it: the next real byte received crosses
+    /// the threshold and fires the IRQ, and the handler discards the primed bytes before
+    /// delivering real data. QEMU fires the RX IRQ on every byte regardless of fill level, so
+    /// this is skipped under the `qemu` feature.
+    #[cfg(not(feature = "qemu"))]
+    fn prime_rx_fifo(&self, count: usize) {
+        self.ITCR.write(ITCR::ITCR::Enabled);
+
+        for _ in 0..count {
+            self.TDR.write(TDR::DATA.val(0));
+        }
+
+        self.ITCR.write(ITCR::ITCR::Disabled);
+
+        unsafe { RX_PRIME_PENDING = count };
+    }
+
+    /// RX interrupt handler: drains the hardware FIFO into the ring buffer `io::Read` consumes.
+    pub fn handle_rx_irq(&self) {
+        if !self.MIS.matches_all(MIS::RXMIS::SET) {
+            return;
+        }
+
+        self.drain_rx_fifo();
+    }
+
+    /// Move every byte currently in the hardware RX FIFO into `RX_QUEUE`, discarding the priming
+    /// bytes injected by [`Self::prime_rx_fifo`] along the way.
+    ///
+    /// Called from [`Self::handle_rx_irq`], and also straight from `io::Read::read` as a polling
+    /// fallback: this kernel has no interrupt controller driver or exception vector table yet
+    /// (see the TODO below `bsp::post_init`), so nothing actually calls `handle_rx_irq` today.
+    fn drain_rx_fifo(&self) {
+        while !self.FR.matches_all(FR::RXFE::SET) {
+            let byte = self.DR.read(DR::DATA) as u8;
+
+            unsafe {
+                if RX_PRIME_PENDING > 0 {
+                    RX_PRIME_PENDING -= 1;
+                    continue;
+                }
+
+                RX_QUEUE.push(byte);
+            }
+        }
+    }
+}
+
+impl driver::Driver for PL011Uart {
+    fn name(&self) -> &str {
+        "PL011Uart"
+    }
+
+    /// Set up baud rate and characteristics using the default `UartConfig`.
+    ///
+    /// Results in 8N1 and 230400 baud (if the clk has been previously set to 48 MHz by the
+    /// firmware).
+    fn init(&self) -> KernelResult {
+        self.configure(&UartConfig::default())
+    }
 }
 
 impl io::Write for PL011Uart {
@@ -226,14 +509,25 @@ impl io::Write for PL011Uart {
 
 impl io::Read for PL011Uart {
     type Err = KernelError;
-    
+
+    /// Pops bytes the RX IRQ handler has already placed in the ring buffer. Since nothing in
+    /// this kernel drives `handle_rx_irq` from an actual interrupt yet, this also polls the
+    /// hardware FIFO directly through the same priming-aware drain path whenever it has data, so
+    /// reads still make progress instead of hanging forever on an empty queue.
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Err> {
-        for byte in buf { 
-            while self.FR.matches_all(FR::RXFE::SET) {
+        for byte in buf {
+            *byte = loop {
+                if let Some(b) = unsafe { RX_QUEUE.pop() } {
+                    break b;
+                }
+
+                if !self.FR.matches_all(FR::RXFE::SET) {
+                    self.drain_rx_fifo();
+                    continue;
+                }
+
                 bsp::nop();
-            }
-    
-            *byte = self.DR.read(DR::DATA) as u8;
+            };
         }
 
         Ok(buf.len())
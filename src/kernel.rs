@@ -1,6 +1,8 @@
 pub mod io;
 pub mod driver;
 pub mod result;
+pub mod chainloader;
+pub mod print;
 
 use crate::{bsp, kernel::io::{Read, Write}};
 
@@ -0,0 +1,104 @@
+//! UART chainloader.
+//!
+//! Turns the kernel into a bootstrap that receives a second-stage kernel image over the console
+//! and jumps to it, so a new image can be tried out without reflashing the SD card.
+//!
+//! # Status: experimental, unsafe on real hardware
+//!
+//! The request this implements calls for relocating the chainloader itself to a fixed upper
+//! address at `_start`, before `kernel::init`, specifically so the incoming image cannot clobber
+//! the running loader. That relocation needs a linker script and an assembly `_start` stub,
+//! neither of which exist anywhere in this kernel, and is **not implemented here**.
+//!
+//! Without it, `LOAD_ADDR` is also this loader's own conventional load address. The incoming
+//! image is staged into `STAGING` (this binary's own `.bss`, past its code) for the whole
+//! (host-speed) receive, but the final `copy_nonoverlapping` into `LOAD_ADDR` still overwrites
+//! the running binary's own code and data — including, for any image past a trivial size, the
+//! instructions this very function is about to execute next. That is a near-certain crash on
+//! real hardware, not an edge case, so this feature requires the separate
+//! `chainloader_unsafe_self_overwrite` feature to be enabled alongside `chainloader` as an
+//! explicit acknowledgement of the risk; it only has a chance of working under emulation (e.g.
+//! QEMU loading the image from a different host-side buffer) or once relocation lands.
+
+use crate::{
+    bsp,
+    kernel::{
+        io::{Read, Write},
+        result::{KernelError, KernelResult},
+    },
+};
+
+#[cfg(all(feature = "chainloader", not(feature = "chainloader_unsafe_self_overwrite")))]
+compile_error!(
+    "the chainloader overwrites its own running code on real hardware (see module docs); enable \
+     `chainloader_unsafe_self_overwrite` alongside `chainloader` to acknowledge this and build it \
+     anyway"
+);
+
+/// Magic bytes the host-side sender transmits before the image size and payload.
+const MAGIC: [u8; 4] = *b"CHNL";
+
+/// Largest image this loader accepts, as a sanity bound rather than a real memory-map limit.
+const MAX_IMAGE_SIZE: usize = 0x0020_0000;
+
+/// Address the received image is loaded to and then jumped to.
+const LOAD_ADDR: usize = 0x8_0000;
+
+/// Byte sent back once the header has been validated and the loader is ready for the payload.
+const READY_TOKEN: u8 = b'R';
+
+/// Scratch buffer the incoming image is received into, kept away from `LOAD_ADDR` so the
+/// (potentially slow, host-paced) receive doesn't overwrite the running loader's own code.
+static mut STAGING: [u8; MAX_IMAGE_SIZE] = [0; MAX_IMAGE_SIZE];
+
+/// Receive a kernel image over `bsp::console()`, load it at `LOAD_ADDR`, and jump to it.
+///
+/// Never returns: control passes to the freshly loaded image.
+pub fn run() -> ! {
+    if let Err(e) = receive_image() {
+        panic!("chainloader: failed to receive image: {:?}", e);
+    }
+
+    unsafe { jump_to_image() }
+}
+
+fn receive_image() -> KernelResult {
+    let console = bsp::console();
+
+    let mut magic = [0u8; MAGIC.len()];
+    console.read_exact::<KernelError>(&mut magic)?;
+    if magic != MAGIC {
+        return Err(KernelError::InvalidImage);
+    }
+
+    let mut size_buf = [0u8; 4];
+    console.read_exact::<KernelError>(&mut size_buf)?;
+    let size = u32::from_le_bytes(size_buf) as usize;
+    if size == 0 || size > MAX_IMAGE_SIZE {
+        return Err(KernelError::InvalidImage);
+    }
+
+    console.write_all::<KernelError>(&[READY_TOKEN])?;
+
+    // Safety: `size` was just bounded by `MAX_IMAGE_SIZE`, `STAGING`'s length.
+    let staging = unsafe { &mut STAGING[..size] };
+    console.read_exact::<KernelError>(staging)?;
+
+    // Safety: `LOAD_ADDR` is the board's designated load address for second-stage images, and
+    // `staging` is exactly `size` bytes, so the copy cannot run past it.
+    unsafe {
+        core::ptr::copy_nonoverlapping(staging.as_ptr(), LOAD_ADDR as *mut u8, size);
+    }
+
+    Ok(())
+}
+
+/// Jump to the image just loaded at `LOAD_ADDR`.
+///
+/// # Safety
+///
+/// The caller must ensure a valid image was already written to `LOAD_ADDR` by `receive_image`.
+unsafe fn jump_to_image() -> ! {
+    let entry: extern "C" fn() -> ! = core::mem::transmute(LOAD_ADDR as *const ());
+    entry()
+}
@@ -0,0 +1,31 @@
+//! Printing macros backed by `bsp::console()`.
+
+use crate::kernel::{io::Write, result::KernelError};
+
+/// Print to the console, like `std::print!`.
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => ($crate::kernel::print::_print(format_args!($($arg)*)));
+}
+
+/// Print to the console with a trailing newline, like `std::println!`.
+#[macro_export]
+macro_rules! println {
+    () => ($crate::print!("\n"));
+    ($($arg:tt)*) => ($crate::print!("{}\n", format_args!($($arg)*)));
+}
+
+/// Print to the console with a trailing newline, like `std::eprintln!`.
+///
+/// There is only one console in this kernel, so this is just an alias for `println!`.
+#[macro_export]
+macro_rules! eprintln {
+    ($($arg:tt)*) => ($crate::println!($($arg)*));
+}
+
+/// Kernel-wide formatting entry point the `print!`/`println!`/`eprintln!` macros expand to.
+#[doc(hidden)]
+pub fn _print(args: core::fmt::Arguments) {
+    let result: Result<(), KernelError> = crate::bsp::console().write_fmt(args);
+    let _ = result;
+}
@@ -0,0 +1,31 @@
+use super::io;
+
+/// Kernel-wide error type returned by drivers and the `io` traits.
+#[derive(Copy, Clone, Debug)]
+pub enum KernelError {
+    EndOfFile,
+    OutOfBounds,
+    FormatError,
+    InvalidConfig,
+    InvalidImage,
+}
+
+impl From<io::EndOfFile> for KernelError {
+    fn from(_: io::EndOfFile) -> Self {
+        KernelError::EndOfFile
+    }
+}
+
+impl From<io::OutOfBounds> for KernelError {
+    fn from(_: io::OutOfBounds) -> Self {
+        KernelError::OutOfBounds
+    }
+}
+
+impl From<io::FormatError> for KernelError {
+    fn from(_: io::FormatError) -> Self {
+        KernelError::FormatError
+    }
+}
+
+pub type KernelResult = Result<(), KernelError>;
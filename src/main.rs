@@ -9,6 +9,17 @@ mod bsp;
 use core::panic::PanicInfo;
 
 #[panic_handler]
-fn panic(_panic: &PanicInfo<'_>) -> ! {
-    loop {}
+fn panic(info: &PanicInfo<'_>) -> ! {
+    use kernel::{io::Write, result::KernelError};
+
+    // Don't go through `bsp::console()`: if the panic happened mid-write to the shared UART,
+    // that `&mut` handle is still in use by the interrupted write. A fresh handle talks to the
+    // same hardware registers directly instead.
+    let mut uart = bsp::uart::PL011Uart {};
+    let result: Result<(), KernelError> = uart.write_fmt(format_args!("\n\nKERNEL PANIC: {}\n", info));
+    let _ = result;
+
+    loop {
+        bsp::nop();
+    }
 }